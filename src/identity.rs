@@ -0,0 +1,183 @@
+use anyhow::{anyhow, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+
+const IDENTITY_FILE: &str = "identity.json";
+const TRUSTED_KEYS_FILE: &str = "trusted_keys.json";
+pub const SHARED_PASSPHRASE_ENV: &str = "SPYFALL_PASSPHRASE";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredIdentity {
+    public_key: String,
+    secret_key: String,
+}
+
+// Derive a signing key deterministically from a shared passphrase via
+// HKDF-SHA256, so every player who knows the phrase arrives at the same
+// ed25519 identity without exchanging keys out of band
+pub fn derive_identity_from_passphrase(passphrase: &str) -> Result<SigningKey> {
+    let hk = Hkdf::<Sha256>::new(None, passphrase.as_bytes());
+    let mut seed = [0u8; 32];
+    hk.expand(b"spyfall-shared-secret-identity", &mut seed)
+        .map_err(|_| anyhow!("Failed to derive identity from passphrase"))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+// Generate a fresh random ed25519 identity for "explicit trust" mode
+pub fn generate_identity() -> SigningKey {
+    SigningKey::generate(&mut OsRng)
+}
+
+// Persist an explicit-trust identity to `identity.json`
+pub fn save_identity(signing_key: &SigningKey) -> Result<()> {
+    let stored = StoredIdentity {
+        public_key: hex::encode(signing_key.verifying_key().to_bytes()),
+        secret_key: hex::encode(signing_key.to_bytes()),
+    };
+    fs::write(IDENTITY_FILE, serde_json::to_string_pretty(&stored)?)?;
+    Ok(())
+}
+
+// Whether this player has an identity configured at all, via either trust
+// mode. Used to distinguish "no identity, so signing is opt-in and silently
+// skipped" from "identity is configured but corrupt," which should surface
+// as a real error instead of being swallowed the same way
+pub fn has_identity_configured() -> bool {
+    std::env::var(SHARED_PASSPHRASE_ENV).is_ok() || std::path::Path::new(IDENTITY_FILE).exists()
+}
+
+// Load this player's identity. A shared-secret passphrase (via
+// `SPYFALL_PASSPHRASE`) takes precedence over the explicit `identity.json`
+// keypair, mirroring the two trust modes
+pub fn load_identity() -> Result<SigningKey> {
+    if let Ok(passphrase) = std::env::var(SHARED_PASSPHRASE_ENV) {
+        return derive_identity_from_passphrase(&passphrase);
+    }
+
+    let content = fs::read_to_string(IDENTITY_FILE).map_err(|_| {
+        anyhow!(
+            "No identity found; run the 'keygen' command first or set {}",
+            SHARED_PASSPHRASE_ENV
+        )
+    })?;
+    let stored: StoredIdentity = serde_json::from_str(&content)?;
+    let secret_bytes =
+        hex::decode(&stored.secret_key).map_err(|_| anyhow!("Invalid hex in identity.json"))?;
+    let secret_array: [u8; 32] = secret_bytes
+        .try_into()
+        .map_err(|_| anyhow!("Invalid secret key length in identity.json"))?;
+    Ok(SigningKey::from_bytes(&secret_array))
+}
+
+// Load the set of public keys this player trusts. In shared-secret mode
+// `trusted_keys.json` isn't needed: everyone who knows the passphrase derives
+// the same public key, so it's implicitly trusted
+pub fn load_trusted_keys() -> Result<Vec<VerifyingKey>> {
+    let mut trusted = Vec::new();
+
+    if let Ok(passphrase) = std::env::var(SHARED_PASSPHRASE_ENV) {
+        trusted.push(derive_identity_from_passphrase(&passphrase)?.verifying_key());
+    }
+
+    if let Ok(content) = fs::read_to_string(TRUSTED_KEYS_FILE) {
+        let keys: Vec<String> = serde_json::from_str(&content)?;
+        for key_hex in keys {
+            let bytes =
+                hex::decode(&key_hex).map_err(|_| anyhow!("Invalid hex in trusted_keys.json"))?;
+            let array: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| anyhow!("Invalid public key length in trusted_keys.json"))?;
+            trusted.push(
+                VerifyingKey::from_bytes(&array)
+                    .map_err(|_| anyhow!("Invalid ed25519 public key in trusted_keys.json"))?,
+            );
+        }
+    }
+
+    Ok(trusted)
+}
+
+// Sign `message` (the canonical bytes of a `Challenge` or `Response`, minus
+// its signature fields) with `signing_key`, returning (signer_pubkey_hex, signature_hex)
+pub fn sign(signing_key: &SigningKey, message: &[u8]) -> (String, String) {
+    let signature: Signature = signing_key.sign(message);
+    (
+        hex::encode(signing_key.verifying_key().to_bytes()),
+        hex::encode(signature.to_bytes()),
+    )
+}
+
+// Verify that `signature_hex` over `message` was produced by `signer_pubkey_hex`,
+// and that the signer is in the trusted set
+pub fn verify(
+    signer_pubkey_hex: &str,
+    signature_hex: &str,
+    message: &[u8],
+    trusted: &[VerifyingKey],
+) -> Result<bool> {
+    let pubkey_bytes =
+        hex::decode(signer_pubkey_hex).map_err(|_| anyhow!("Invalid hex in signer_pubkey"))?;
+    let pubkey_array: [u8; 32] = pubkey_bytes
+        .try_into()
+        .map_err(|_| anyhow!("Invalid public key length in signer_pubkey"))?;
+    let verifying_key = VerifyingKey::from_bytes(&pubkey_array)
+        .map_err(|_| anyhow!("Invalid ed25519 public key in signer_pubkey"))?;
+
+    if !trusted.iter().any(|k| k == &verifying_key) {
+        return Ok(false);
+    }
+
+    let sig_bytes = hex::decode(signature_hex).map_err(|_| anyhow!("Invalid hex in signature"))?;
+    let sig_array: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| anyhow!("Invalid signature length"))?;
+    let signature = Signature::from_bytes(&sig_array);
+
+    Ok(verifying_key.verify(message, &signature).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_verify_round_trip() {
+        let signing_key = generate_identity();
+        let message = b"challenge-bytes";
+        let (signer_pubkey, signature) = sign(&signing_key, message);
+        let trusted = vec![signing_key.verifying_key()];
+        assert!(verify(&signer_pubkey, &signature, message, &trusted).unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_untrusted_signer() {
+        let signing_key = generate_identity();
+        let message = b"challenge-bytes";
+        let (signer_pubkey, signature) = sign(&signing_key, message);
+        // Trusted set only contains some other identity, not the one that signed
+        let trusted = vec![generate_identity().verifying_key()];
+        assert!(!verify(&signer_pubkey, &signature, message, &trusted).unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_message() {
+        let signing_key = generate_identity();
+        let (signer_pubkey, signature) = sign(&signing_key, b"challenge-bytes");
+        let trusted = vec![signing_key.verifying_key()];
+        assert!(!verify(&signer_pubkey, &signature, b"tampered-bytes", &trusted).unwrap());
+    }
+
+    #[test]
+    fn test_derive_identity_from_passphrase_is_deterministic() {
+        let a = derive_identity_from_passphrase("correct horse battery staple").unwrap();
+        let b = derive_identity_from_passphrase("correct horse battery staple").unwrap();
+        assert_eq!(a.verifying_key(), b.verifying_key());
+
+        let c = derive_identity_from_passphrase("a different passphrase").unwrap();
+        assert_ne!(a.verifying_key(), c.verifying_key());
+    }
+}
@@ -11,8 +11,14 @@ use sha2::{Digest, Sha256};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+mod identity;
+use identity::has_identity_configured;
+pub use identity::{generate_identity, load_identity, save_identity};
+
 const LOCATIONS_FILE: &str = "locations.json";
-const PRIME_BITS: usize = 48; // The more bits, the longer it takes to factor
+const DIFFICULTIES_FILE: &str = "difficulties.json";
+const DEFAULT_PRIME_BITS: u32 = 48; // The more bits, the longer it takes to factor
+const MIN_PRIME_BITS: u32 = 24; // Below this, trial division (up to 1e6 ~ 20 bits) wins trivially
 
 // Default locations list (fallback if locations.json doesn't exist)
 const DEFAULT_LOCATIONS: &[&str] = &[
@@ -50,12 +56,138 @@ const DEFAULT_LOCATIONS: &[&str] = &[
 pub struct Challenge {
     pub semiprimes: Vec<String>, // Hex-encoded semiprimes
     pub id: String,              // Unique challenge ID
+    pub difficulties: Vec<u32>, // Compact (mantissa+exponent) difficulty tag per semiprime, same length as `semiprimes`
+    #[serde(default)]
+    pub signer_pubkey: Option<String>, // Hex-encoded ed25519 public key of the originator
+    #[serde(default)]
+    pub signature: Option<String>, // Hex-encoded ed25519 signature over the fields above
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Response {
     pub encrypted_location: String, // Hex-encoded encrypted location
     pub challenge_id: String,
+    pub proof_t: String, // Hex-encoded commitment `t` for the factorization proof
+    pub proof_z: String, // Hex-encoded response `z` for the factorization proof
+    #[serde(default)]
+    pub signer_pubkey: Option<String>, // Hex-encoded ed25519 public key of the originator
+    #[serde(default)]
+    pub signature: Option<String>, // Hex-encoded ed25519 signature over the fields above
+}
+
+// The subset of `Challenge`/`Response` fields that get signed — everything
+// except the signature fields themselves, serialized the same way on both
+// the signing and verifying side so the canonical bytes always match
+#[derive(Serialize)]
+struct ChallengeSigningPayload<'a> {
+    semiprimes: &'a [String],
+    id: &'a str,
+    difficulties: &'a [u32],
+}
+
+#[derive(Serialize)]
+struct ResponseSigningPayload<'a> {
+    encrypted_location: &'a str,
+    challenge_id: &'a str,
+    proof_t: &'a str,
+    proof_z: &'a str,
+}
+
+fn challenge_signing_bytes(challenge: &Challenge) -> Result<Vec<u8>> {
+    let payload = ChallengeSigningPayload {
+        semiprimes: &challenge.semiprimes,
+        id: &challenge.id,
+        difficulties: &challenge.difficulties,
+    };
+    Ok(serde_json::to_vec(&payload)?)
+}
+
+fn response_signing_bytes(response: &Response) -> Result<Vec<u8>> {
+    let payload = ResponseSigningPayload {
+        encrypted_location: &response.encrypted_location,
+        challenge_id: &response.challenge_id,
+        proof_t: &response.proof_t,
+        proof_z: &response.proof_z,
+    };
+    Ok(serde_json::to_vec(&payload)?)
+}
+
+// Sign a `Challenge` in place with this player's identity, if one is
+// configured. No identity configured is not an error — signing is opt-in —
+// but an identity that *is* configured and fails to load (corrupt JSON, bad
+// hex, wrong key length) is a real error and must not be mistaken for "no
+// identity configured"
+fn try_sign_challenge(challenge: &mut Challenge) -> Result<bool> {
+    if !has_identity_configured() {
+        return Ok(false);
+    }
+    let signing_key = load_identity()?;
+    let bytes = challenge_signing_bytes(challenge)?;
+    let (signer_pubkey, signature) = identity::sign(&signing_key, &bytes);
+    challenge.signer_pubkey = Some(signer_pubkey);
+    challenge.signature = Some(signature);
+    Ok(true)
+}
+
+// Sign a `Response` in place with this player's identity, mirroring
+// `try_sign_challenge`
+fn try_sign_response(response: &mut Response) -> Result<bool> {
+    if !has_identity_configured() {
+        return Ok(false);
+    }
+    let signing_key = load_identity()?;
+    let bytes = response_signing_bytes(response)?;
+    let (signer_pubkey, signature) = identity::sign(&signing_key, &bytes);
+    response.signer_pubkey = Some(signer_pubkey);
+    response.signature = Some(signature);
+    Ok(true)
+}
+
+// Verify a `Challenge`'s signature against the trusted key set. Returns
+// `Ok(())` if the challenge is unsigned (unauthenticated, but allowed) or
+// signed by a trusted key; errors if it's signed by an unknown key
+fn require_trusted_challenge(challenge: &Challenge) -> Result<()> {
+    match (&challenge.signer_pubkey, &challenge.signature) {
+        (Some(signer_pubkey), Some(signature)) => {
+            let trusted = identity::load_trusted_keys()?;
+            let bytes = challenge_signing_bytes(challenge)?;
+            if identity::verify(signer_pubkey, signature, &bytes, &trusted)? {
+                println!("🔏 Challenge signature verified ({})", signer_pubkey);
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "Challenge signature is invalid or from an untrusted key"
+                ))
+            }
+        }
+        _ => {
+            println!("⚠️  Challenge is unauthenticated (no signature)");
+            Ok(())
+        }
+    }
+}
+
+// Verify a `Response`'s signature against the trusted key set, mirroring
+// `require_trusted_challenge`
+fn require_trusted_response(response: &Response) -> Result<()> {
+    match (&response.signer_pubkey, &response.signature) {
+        (Some(signer_pubkey), Some(signature)) => {
+            let trusted = identity::load_trusted_keys()?;
+            let bytes = response_signing_bytes(response)?;
+            if identity::verify(signer_pubkey, signature, &bytes, &trusted)? {
+                println!("🔏 Response signature verified ({})", signer_pubkey);
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "Response signature is invalid or from an untrusted key"
+                ))
+            }
+        }
+        _ => {
+            println!("⚠️  Response is unauthenticated (no signature)");
+            Ok(())
+        }
+    }
 }
 
 // Load locations from config file or use default list
@@ -72,6 +204,90 @@ fn load_locations() -> Result<Vec<String>> {
     }
 }
 
+// Load per-location difficulty (in prime bits, pre-packing) from the optional
+// `difficulties.json` file, sized to `location_count`. Falls back to
+// `DEFAULT_PRIME_BITS` for every location if the file doesn't exist
+fn load_difficulty_bits(location_count: usize) -> Result<Vec<u32>> {
+    match std::fs::read_to_string(DIFFICULTIES_FILE) {
+        Ok(content) => {
+            let bits: Vec<u32> = serde_json::from_str(&content)?;
+            if bits.is_empty() {
+                return Err(anyhow!("difficulties.json must not be empty"));
+            }
+            Ok((0..location_count)
+                .map(|i| bits[i % bits.len()].max(MIN_PRIME_BITS))
+                .collect())
+        }
+        Err(_) => Ok(vec![DEFAULT_PRIME_BITS; location_count]),
+    }
+}
+
+// Pack a prime bit-length into a compact mantissa+exponent tag, Bitcoin
+// "nBits"-style: `bits = mantissa << exponent`. This leaves room for future
+// difficulty schemes (e.g. an actual PoW target) to reuse the same u32 field
+// instead of a raw bit count
+fn pack_difficulty(bits: u32) -> u32 {
+    let mut mantissa = bits;
+    let mut exponent: u32 = 0;
+    while mantissa > 0x00FF_FFFF {
+        mantissa >>= 1;
+        exponent += 1;
+    }
+    (exponent << 24) | mantissa
+}
+
+// Unpack a compact difficulty tag back into a prime bit-length. Tags come
+// straight off the wire (an arbitrary, possibly-unauthenticated `Challenge`),
+// so a malformed exponent is rejected instead of shifted blindly, which would
+// panic ("attempt to shift left with overflow") for `exponent >= 32`
+fn unpack_difficulty(tag: u32) -> Result<u32> {
+    let exponent = tag >> 24;
+    let mantissa = tag & 0x00FF_FFFF;
+    mantissa.checked_shl(exponent).ok_or_else(|| {
+        anyhow!(
+            "Invalid difficulty tag {:#010x}: exponent {} is out of range",
+            tag,
+            exponent
+        )
+    })
+}
+
+// Verify that a challenge's declared difficulty tags actually match the bit
+// lengths of its semiprimes, so a challenge can't claim to be easier (or
+// harder) than the numbers it actually contains
+fn validate_challenge_difficulty(challenge: &Challenge) -> Result<()> {
+    if challenge.difficulties.len() != challenge.semiprimes.len() {
+        return Err(anyhow!(
+            "Challenge has {} difficulty tags but {} semiprimes",
+            challenge.difficulties.len(),
+            challenge.semiprimes.len()
+        ));
+    }
+
+    for (tag, semiprime_hex) in challenge.difficulties.iter().zip(&challenge.semiprimes) {
+        let declared_bits = unpack_difficulty(*tag)?;
+        let semiprime_bytes =
+            hex::decode(semiprime_hex).map_err(|_| anyhow!("Invalid hex in semiprime"))?;
+        let n = BigUint::from_bytes_be(&semiprime_bytes);
+
+        // n = p*q with p,q roughly `declared_bits` long, so n should be
+        // roughly 2*declared_bits long; allow slack for carries
+        let expected_bits = declared_bits
+            .checked_mul(2)
+            .ok_or_else(|| anyhow!("Difficulty tag implies an absurd bit length"))?;
+        let actual_bits = n.bits() as u32;
+        if actual_bits.abs_diff(expected_bits) > 2 {
+            return Err(anyhow!(
+                "Semiprime bit length ({}) doesn't match declared difficulty ({} bits per prime)",
+                actual_bits,
+                declared_bits
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 // Generate a random prime of specified bit length
 fn generate_prime(bits: usize) -> BigUint {
     let mut rng = rand::thread_rng();
@@ -235,6 +451,88 @@ fn bigint_to_aes_key(n: &BigUint) -> [u8; 32] {
     hasher.finalize().into()
 }
 
+// Pick the smallest generator `g >= 2` coprime to `n` (deterministic so both
+// prover and verifier derive the same base without exchanging it)
+fn select_generator(n: &BigUint) -> BigUint {
+    let mut g = BigUint::from(2u32);
+    while gcd(&g, n) != BigUint::one() {
+        g += BigUint::one();
+    }
+    g
+}
+
+// Fiat-Shamir challenge for the proof of knowledge of factorization, bound to
+// the challenge id, modulus, generator, commitment, and the claimed encrypted
+// location so a proof can't be replayed against a different response
+fn proof_challenge(
+    challenge_id: &str,
+    n: &BigUint,
+    g: &BigUint,
+    t: &BigUint,
+    encrypted_location: &str,
+) -> BigUint {
+    let mut hasher = Sha256::new();
+    hasher.update(challenge_id.as_bytes());
+    hasher.update(n.to_bytes_be());
+    hasher.update(g.to_bytes_be());
+    hasher.update(t.to_bytes_be());
+    hasher.update(encrypted_location.as_bytes());
+    let digest = hasher.finalize();
+    BigUint::from_bytes_be(&digest) % (BigUint::one() << 128usize)
+}
+
+// Girault-Poupard-Stern / Schnorr-style proof that the prover knows `s = n -
+// phi(n) = p + q - 1` for `n = p*q`, without revealing `p` or `q`. Knowing `s`
+// together with `n` is equivalent to knowing the factorization, since `p` and
+// `q` are the roots of `x^2 - (s+1)x + n = 0`. Verification is a couple of
+// modular exponentiations instead of re-running `factor_semiprime`.
+fn prove_factorization(
+    n: &BigUint,
+    p: &BigUint,
+    q: &BigUint,
+    challenge_id: &str,
+    encrypted_location: &str,
+) -> (BigUint, BigUint) {
+    let s = p + q - BigUint::one();
+    let g = select_generator(n);
+
+    let mut rng = rand::thread_rng();
+    let r_bound = BigUint::one() << (s.bits() as usize + 128);
+    let r = generate_random_range(&BigUint::zero(), &r_bound, &mut rng);
+
+    let t = mod_exp(&g, &r, n);
+    let e = proof_challenge(challenge_id, n, &g, &t, encrypted_location);
+    let z = r + e * s;
+
+    (t, z)
+}
+
+// Verify a proof of knowledge of factorization produced by `prove_factorization`
+fn verify_factorization_proof(
+    n: &BigUint,
+    t: &BigUint,
+    z: &BigUint,
+    challenge_id: &str,
+    encrypted_location: &str,
+) -> bool {
+    if gcd(t, n) != BigUint::one() {
+        return false;
+    }
+    // s has roughly half the bit length of n, so r + e*s can't reasonably
+    // exceed n's bit length plus the challenge and randomness margins
+    if z.bits() > n.bits() + 300 {
+        return false;
+    }
+
+    let g = select_generator(n);
+    let h = mod_exp(&g, n, n);
+    let e = proof_challenge(challenge_id, n, &g, t, encrypted_location);
+
+    let lhs = mod_exp(&g, z, n);
+    let rhs = (t * mod_exp(&h, &e, n)) % n;
+    lhs == rhs
+}
+
 // Encode challenge as base64 string
 fn encode_challenge_base64(challenge: &Challenge) -> Result<String> {
     let json = serde_json::to_string(challenge)?;
@@ -321,16 +619,23 @@ fn decrypt_location(encrypted_hex: &str, key: &BigUint) -> Result<String> {
     String::from_utf8(plaintext).map_err(|_| anyhow!("Invalid UTF-8 in decrypted data"))
 }
 
-// Deterministically select semiprime based on location
-fn select_semiprime_for_location(semiprimes: &[String], location: &str) -> Result<BigUint> {
+// Deterministically map a location to an index into a challenge's per-location
+// vectors (semiprimes, difficulties), based on its position in the sorted
+// locations list
+fn location_index(location: &str) -> Result<usize> {
     let locations = load_locations()?;
     let mut sorted_locations = locations.clone();
     sorted_locations.sort();
 
-    let index = sorted_locations
+    sorted_locations
         .iter()
         .position(|loc| loc == location)
-        .ok_or_else(|| anyhow!("Location '{}' not found in locations list", location))?;
+        .ok_or_else(|| anyhow!("Location '{}' not found in locations list", location))
+}
+
+// Deterministically select semiprime based on location
+fn select_semiprime_for_location(semiprimes: &[String], location: &str) -> Result<BigUint> {
+    let index = location_index(location)?;
 
     let semiprime_index = index % semiprimes.len();
     let semiprime_hex = &semiprimes[semiprime_index];
@@ -340,6 +645,13 @@ fn select_semiprime_for_location(semiprimes: &[String], location: &str) -> Resul
     Ok(BigUint::from_bytes_be(&semiprime_bytes))
 }
 
+// Deterministically select the difficulty tag for a location, using the same
+// indexing scheme as `select_semiprime_for_location`
+fn select_difficulty_for_location(difficulties: &[u32], location: &str) -> Result<u32> {
+    let index = location_index(location)?;
+    Ok(difficulties[index % difficulties.len()])
+}
+
 pub fn handle_challenge(location: &str) -> Result<()> {
     let locations = load_locations()?;
     if !locations.contains(&location.to_string()) {
@@ -356,16 +668,23 @@ pub fn handle_challenge(location: &str) -> Result<()> {
         challenge_size
     );
 
+    let difficulty_bits = load_difficulty_bits(challenge_size)?;
+
     let mut semiprimes = Vec::new();
+    let mut difficulties = Vec::new();
     for i in 0..challenge_size {
         if i % 5 == 0 && i > 0 {
             println!("Generated {}/{} semiprimes...", i, challenge_size);
         }
 
-        let p = generate_prime(PRIME_BITS);
-        let q = generate_prime(PRIME_BITS);
+        let bits = difficulty_bits[i % difficulty_bits.len()];
+        let p = generate_prime(bits as usize);
+        let q = generate_prime(bits as usize);
         let semiprime = &p * &q;
         semiprimes.push(hex::encode(semiprime.to_bytes_be()));
+        // generate_prime rounds up to a whole number of bytes, so tag the
+        // primes' actual bit length rather than the requested one
+        difficulties.push(pack_difficulty(p.bits() as u32));
     }
 
     let challenge_id = hex::encode(Sha256::digest(format!(
@@ -376,11 +695,20 @@ pub fn handle_challenge(location: &str) -> Result<()> {
             .unwrap()
             .as_secs()
     )));
-    let challenge = Challenge {
+    let mut challenge = Challenge {
         semiprimes,
         id: challenge_id,
+        difficulties,
+        signer_pubkey: None,
+        signature: None,
     };
 
+    if try_sign_challenge(&mut challenge)? {
+        println!("🔏 Challenge signed with your identity");
+    } else {
+        println!("⚠️  No identity configured; challenge will be unauthenticated (run 'keygen' or set {})", identity::SHARED_PASSPHRASE_ENV);
+    }
+
     let base64_challenge = encode_challenge_base64(&challenge)?;
 
     println!("\n🎯 Challenge generated successfully!");
@@ -395,6 +723,9 @@ pub fn handle_challenge(location: &str) -> Result<()> {
 
 pub fn handle_respond(challenge_input: &str, location: &str) -> Result<()> {
     let challenge = decode_challenge(challenge_input)?;
+    require_trusted_challenge(&challenge)?;
+    validate_challenge_difficulty(&challenge)?;
+
     let locations = load_locations()?;
 
     if !locations.contains(&location.to_string()) {
@@ -406,8 +737,15 @@ pub fn handle_respond(challenge_input: &str, location: &str) -> Result<()> {
 
     println!("🔍 Finding semiprime for location: {}", location);
     let semiprime = select_semiprime_for_location(&challenge.semiprimes, location)?;
+    let difficulty_bits = unpack_difficulty(select_difficulty_for_location(
+        &challenge.difficulties,
+        location,
+    )?)?;
 
-    println!("⚡ Performing proof-of-work (factoring semiprime)...");
+    println!(
+        "⚡ Performing proof-of-work (factoring semiprime, difficulty {} bits)...",
+        difficulty_bits
+    );
     println!("⏳ This will take a moment...");
 
     let start = std::time::Instant::now();
@@ -417,16 +755,33 @@ pub fn handle_respond(challenge_input: &str, location: &str) -> Result<()> {
     println!("✅ Factorization complete in {:.2}s", elapsed.as_secs_f64());
 
     // Use the smaller prime for encryption
-    let (smaller, _larger) = if p < q { (p, q) } else { (q, p) };
+    let smaller = if p < q { p.clone() } else { q.clone() };
 
     println!("🔐 Encrypting location...");
     let encrypted_location = encrypt_location(location, &smaller)?;
 
-    let response = Response {
+    println!("🧾 Generating proof of knowledge of factorization...");
+    let (proof_t, proof_z) =
+        prove_factorization(&semiprime, &p, &q, &challenge.id, &encrypted_location);
+
+    let mut response = Response {
         encrypted_location,
         challenge_id: challenge.id,
+        proof_t: hex::encode(proof_t.to_bytes_be()),
+        proof_z: hex::encode(proof_z.to_bytes_be()),
+        signer_pubkey: None,
+        signature: None,
     };
 
+    if try_sign_response(&mut response)? {
+        println!("🔏 Response signed with your identity");
+    } else {
+        println!(
+            "⚠️  No identity configured; response will be unauthenticated (run 'keygen' or set {})",
+            identity::SHARED_PASSPHRASE_ENV
+        );
+    }
+
     let base64_response = encode_response_base64(&response)?;
 
     println!("📤 Response generated successfully!");
@@ -441,6 +796,9 @@ pub fn handle_respond(challenge_input: &str, location: &str) -> Result<()> {
 pub fn handle_verify(challenge_input: &str, response_input: &str, location: &str) -> Result<()> {
     let challenge = decode_challenge(challenge_input)?;
     let response = decode_response(response_input)?;
+    require_trusted_challenge(&challenge)?;
+    require_trusted_response(&response)?;
+    validate_challenge_difficulty(&challenge)?;
 
     if challenge.id != response.challenge_id {
         return Err(anyhow!("Challenge ID mismatch"));
@@ -459,46 +817,46 @@ pub fn handle_verify(challenge_input: &str, response_input: &str, location: &str
     // Select the correct semiprime for the known location
     let semiprime = select_semiprime_for_location(&challenge.semiprimes, location)?;
 
-    println!("⚡ Performing proof-of-work for {}...", location);
+    let proof_t_bytes =
+        hex::decode(&response.proof_t).map_err(|_| anyhow!("Invalid hex in proof_t"))?;
+    let proof_z_bytes =
+        hex::decode(&response.proof_z).map_err(|_| anyhow!("Invalid hex in proof_z"))?;
+    let proof_t = BigUint::from_bytes_be(&proof_t_bytes);
+    let proof_z = BigUint::from_bytes_be(&proof_z_bytes);
+
+    println!(
+        "⚡ Checking proof of knowledge of factorization for {}...",
+        location
+    );
     let start = std::time::Instant::now();
 
-    let (p, q) = factor_semiprime(&semiprime)?;
+    let valid = verify_factorization_proof(
+        &semiprime,
+        &proof_t,
+        &proof_z,
+        &response.challenge_id,
+        &response.encrypted_location,
+    );
     let elapsed = start.elapsed();
 
-    println!("✅ Factorization complete in {:.2}s", elapsed.as_secs_f64());
+    println!("✅ Proof check complete in {:.4}s", elapsed.as_secs_f64());
 
-    // Try both primes as decryption keys
-    let keys = [&p, &q];
-    for key in &keys {
-        match decrypt_location(&response.encrypted_location, key) {
-            Ok(decrypted) => {
-                if decrypted == location {
-                    println!("🎉 VERIFICATION SUCCESSFUL!");
-                    println!("✅ The responder knows the location: {}", location);
-                    return Ok(());
-                } else {
-                    println!("❌ VERIFICATION FAILED!");
-                    println!(
-                        "🕵️ Decrypted location '{}' doesn't match expected location '{}'",
-                        decrypted, location
-                    );
-                    println!("🕵️ The responder appears to be the spy (doesn't know the location)");
-                    return Ok(());
-                }
-            }
-            Err(_) => continue,
-        }
+    if valid {
+        println!("🎉 VERIFICATION SUCCESSFUL!");
+        println!("✅ The responder knows the location: {}", location);
+    } else {
+        println!("❌ VERIFICATION FAILED!");
+        println!("🕵️ The responder appears to be the spy (doesn't know the location)");
     }
-
-    println!("❌ VERIFICATION FAILED!");
-    println!("🕵️ Could not decrypt the response with the correct key");
-    println!("🕵️ The responder appears to be the spy (doesn't know the location)");
     Ok(())
 }
 
 pub fn handle_brute(challenge_input: &str, response_input: &str) -> Result<()> {
     let challenge = decode_challenge(challenge_input)?;
     let response = decode_response(response_input)?;
+    require_trusted_challenge(&challenge)?;
+    require_trusted_response(&response)?;
+    validate_challenge_difficulty(&challenge)?;
 
     if challenge.id != response.challenge_id {
         return Err(anyhow!("Challenge ID mismatch"));
@@ -626,25 +984,223 @@ pub fn handle_locations() -> Result<()> {
     Ok(())
 }
 
+// Set up this player's signing identity. With `SPYFALL_PASSPHRASE` set
+// (shared-secret mode), the identity is derived on the fly and nothing is
+// persisted. Otherwise a fresh keypair is generated and saved to
+// `identity.json` (explicit-trust mode).
+pub fn handle_keygen() -> Result<()> {
+    if let Ok(passphrase) = std::env::var(identity::SHARED_PASSPHRASE_ENV) {
+        let signing_key = identity::derive_identity_from_passphrase(&passphrase)?;
+        println!(
+            "🔑 Identity derived from {} (shared-secret mode); nothing was saved",
+            identity::SHARED_PASSPHRASE_ENV
+        );
+        println!(
+            "📋 Public key: {}",
+            hex::encode(signing_key.verifying_key().to_bytes())
+        );
+        return Ok(());
+    }
+
+    let signing_key = generate_identity();
+    save_identity(&signing_key)?;
+
+    println!("🔑 Generated a new identity and saved it to identity.json");
+    println!("📋 Share your public key with other players so they can trust you:");
+    println!("================================================================================");
+    println!("{}", hex::encode(signing_key.verifying_key().to_bytes()));
+    println!("================================================================================");
+
+    Ok(())
+}
+
+// Print this player's public key, for sharing with others so they can add
+// it to their `trusted_keys.json`
+pub fn handle_pubkey() -> Result<()> {
+    let signing_key = load_identity()?;
+    println!("{}", hex::encode(signing_key.verifying_key().to_bytes()));
+    Ok(())
+}
+
+// Factor `n` on a background thread and wait at most `cap` for it to finish.
+// Pollard's rho scales badly with prime size, so a calibration probe a few
+// bits above the sweet spot can run far longer than any reasonable target;
+// this bounds a single round instead of letting `handle_calibrate` hang.
+fn factor_semiprime_with_timeout(n: &BigUint, cap: std::time::Duration) -> Option<f64> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let n = n.clone();
+    std::thread::spawn(move || {
+        let start = std::time::Instant::now();
+        let _ = factor_semiprime(&n);
+        let _ = tx.send(start.elapsed().as_secs_f64());
+    });
+    rx.recv_timeout(cap).ok()
+}
+
+// Benchmark `factor_semiprime` on this machine and recommend a prime
+// bit-length whose factoring time is close to `target_seconds`, via a short
+// binary search. Prints the recommended bit-length (the value
+// `difficulties.json` takes) and its compact difficulty tag.
+pub fn handle_calibrate(target_seconds: f64) -> Result<()> {
+    if target_seconds <= 0.0 {
+        return Err(anyhow!("Target seconds must be positive"));
+    }
+
+    println!(
+        "🎚️  Calibrating PoW difficulty for ~{:.1}s per location...",
+        target_seconds
+    );
+
+    // Any round slower than this is "too slow" regardless of how much slower;
+    // there's no need to wait out the full factoring to learn that.
+    let cap = std::time::Duration::from_secs_f64((target_seconds * 10.0).max(30.0));
+
+    let mut low = MIN_PRIME_BITS;
+    let mut high = MIN_PRIME_BITS + 64;
+    let mut best = low;
+
+    for round in 0..10 {
+        let mid = low + (high - low) / 2;
+        print!("🧪 Round {}: trying {} bits per prime... ", round + 1, mid);
+
+        let p = generate_prime(mid as usize);
+        let q = generate_prime(mid as usize);
+        let semiprime = &p * &q;
+
+        match factor_semiprime_with_timeout(&semiprime, cap) {
+            Some(elapsed) => {
+                println!("{:.2}s", elapsed);
+                best = mid;
+                if elapsed < target_seconds {
+                    low = mid + 1;
+                } else {
+                    high = mid.saturating_sub(1).max(MIN_PRIME_BITS);
+                }
+            }
+            None => {
+                println!(
+                    "> {:.0}s (too slow, giving up on this round)",
+                    cap.as_secs_f64()
+                );
+                high = mid.saturating_sub(1).max(MIN_PRIME_BITS);
+            }
+        }
+
+        if low >= high {
+            break;
+        }
+    }
+
+    let bits = best.max(MIN_PRIME_BITS);
+    let tag = pack_difficulty(bits);
+
+    println!(
+        "\n✅ Recommended difficulty: {} bits per prime (use in difficulties.json)",
+        bits
+    );
+    println!("📋 Difficulty tag: {}", tag);
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn small_semiprime() -> (BigUint, BigUint, BigUint) {
+        let p = generate_prime(16);
+        let q = generate_prime(16);
+        let n = &p * &q;
+        (n, p, q)
+    }
+
+    #[test]
+    fn test_factorization_proof_round_trip() {
+        let (n, p, q) = small_semiprime();
+        let (t, z) = prove_factorization(&n, &p, &q, "challenge-1", "deadbeef");
+        assert!(verify_factorization_proof(
+            &n,
+            &t,
+            &z,
+            "challenge-1",
+            "deadbeef"
+        ));
+    }
+
+    #[test]
+    fn test_factorization_proof_rejects_wrong_challenge_id() {
+        let (n, p, q) = small_semiprime();
+        let (t, z) = prove_factorization(&n, &p, &q, "challenge-1", "deadbeef");
+        assert!(!verify_factorization_proof(
+            &n,
+            &t,
+            &z,
+            "challenge-2",
+            "deadbeef"
+        ));
+    }
+
+    #[test]
+    fn test_factorization_proof_rejects_tampered_z() {
+        let (n, p, q) = small_semiprime();
+        let (t, z) = prove_factorization(&n, &p, &q, "challenge-1", "deadbeef");
+        let tampered_z = z + BigUint::one();
+        assert!(!verify_factorization_proof(
+            &n,
+            &t,
+            &tampered_z,
+            "challenge-1",
+            "deadbeef"
+        ));
+    }
+
+    #[test]
+    fn test_pack_unpack_difficulty_round_trip() {
+        for bits in [MIN_PRIME_BITS, 32, 48, 64, 128] {
+            let tag = pack_difficulty(bits);
+            assert_eq!(unpack_difficulty(tag).unwrap(), bits);
+        }
+    }
+
+    #[test]
+    fn test_unpack_difficulty_rejects_out_of_range_exponent() {
+        let tag = (32u32 << 24) | 1;
+        assert!(unpack_difficulty(tag).is_err());
+    }
+
+    fn challenge_with(semiprimes: Vec<String>, difficulties: Vec<u32>) -> Challenge {
+        Challenge {
+            semiprimes,
+            id: "test-challenge".to_string(),
+            difficulties,
+            signer_pubkey: None,
+            signature: None,
+        }
+    }
+
     #[test]
-    fn test_challenge_function() {
-        let result = challenge("test input".to_string());
-        assert!(result.is_ok());
+    fn test_validate_challenge_difficulty_rejects_length_mismatch() {
+        let (n, _, _) = small_semiprime();
+        let semiprime_hex = hex::encode(n.to_bytes_be());
+        let challenge = challenge_with(vec![semiprime_hex], vec![]);
+        assert!(validate_challenge_difficulty(&challenge).is_err());
     }
 
     #[test]
-    fn test_respond_function() {
-        let result = respond("test input".to_string());
-        assert!(result.is_ok());
+    fn test_validate_challenge_difficulty_rejects_bit_length_mismatch() {
+        let (n, _, _) = small_semiprime();
+        let semiprime_hex = hex::encode(n.to_bytes_be());
+        // n is built from two 16-bit primes, so its declared difficulty
+        // should be nowhere near this wildly larger tag
+        let challenge = challenge_with(vec![semiprime_hex], vec![pack_difficulty(128)]);
+        assert!(validate_challenge_difficulty(&challenge).is_err());
     }
 
     #[test]
-    fn test_verify_function() {
-        let result = verify("test input".to_string());
-        assert!(result.is_ok());
+    fn test_validate_challenge_difficulty_accepts_matching_tag() {
+        let (n, _, _) = small_semiprime();
+        let semiprime_hex = hex::encode(n.to_bytes_be());
+        let challenge = challenge_with(vec![semiprime_hex], vec![pack_difficulty(16)]);
+        assert!(validate_challenge_difficulty(&challenge).is_ok());
     }
 }
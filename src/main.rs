@@ -1,6 +1,9 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use spyfall::{handle_brute, handle_challenge, handle_locations, handle_respond, handle_verify};
+use spyfall::{
+    handle_brute, handle_calibrate, handle_challenge, handle_keygen, handle_locations,
+    handle_pubkey, handle_respond, handle_verify,
+};
 
 #[derive(Parser)]
 #[command(name = "spyfall")]
@@ -43,6 +46,15 @@ enum Commands {
     },
     /// List all available locations
     Locations,
+    /// Generate (or derive) a signing identity for authenticating challenges and responses
+    Keygen,
+    /// Print this player's public key, to share so others can trust it
+    Pubkey,
+    /// Benchmark this machine and recommend a PoW difficulty for a target time per location
+    Calibrate {
+        /// Target wall-clock seconds to factor one location's semiprime
+        target_seconds: f64,
+    },
 }
 
 fn main() -> Result<()> {
@@ -64,5 +76,8 @@ fn main() -> Result<()> {
             response,
         } => handle_brute(&challenge, &response),
         Commands::Locations => handle_locations(),
+        Commands::Keygen => handle_keygen(),
+        Commands::Pubkey => handle_pubkey(),
+        Commands::Calibrate { target_seconds } => handle_calibrate(target_seconds),
     }
 }